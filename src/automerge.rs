@@ -0,0 +1,194 @@
+use automerge::{
+    marks::{ExpandMark, Mark, MarkSet},
+    transaction::Transactable,
+    AutoCommit, ObjType, ReadDoc, ScalarValue,
+};
+use std::{collections::HashMap, ops::Range};
+
+use crate::{Crdt, MarkSpan, MarkValue, Unsupported};
+
+fn mark_value_to_scalar(value: &MarkValue) -> ScalarValue {
+    match value {
+        MarkValue::Bool(b) => ScalarValue::Boolean(*b),
+        MarkValue::Str(s) => ScalarValue::Str(s.as_str().into()),
+    }
+}
+
+fn scalar_to_mark_value(value: &ScalarValue) -> Option<MarkValue> {
+    match value {
+        ScalarValue::Boolean(b) => Some(MarkValue::Bool(*b)),
+        ScalarValue::Str(s) => Some(MarkValue::Str(s.to_string())),
+        _ => None,
+    }
+}
+
+pub struct AutomergeDoc {
+    doc: AutoCommit,
+    text: automerge::ObjId,
+    list: automerge::ObjId,
+    map: automerge::ObjId,
+}
+
+impl Crdt for AutomergeDoc {
+    type Version = Vec<automerge::ChangeHash>;
+
+    fn name() -> &'static str {
+        "automerge"
+    }
+
+    fn create(_gc: bool, _compression: bool) -> Self {
+        let mut doc = AutoCommit::new();
+        let text = doc
+            .put_object(automerge::ROOT, "text", ObjType::Text)
+            .unwrap();
+        let list = doc
+            .put_object(automerge::ROOT, "list", ObjType::List)
+            .unwrap();
+        let map = doc
+            .put_object(automerge::ROOT, "map", ObjType::Map)
+            .unwrap();
+        AutomergeDoc {
+            doc,
+            text,
+            list,
+            map,
+        }
+    }
+
+    fn gc(&self) -> Result<bool, bool> {
+        Err(false)
+    }
+
+    fn compression(&self) -> Result<bool, bool> {
+        Err(false)
+    }
+
+    fn text_insert(&mut self, pos: usize, text: &str) {
+        self.doc.splice_text(&self.text, pos, 0, text).unwrap();
+    }
+
+    fn text_del(&mut self, pos: usize, len: usize) {
+        self.doc
+            .splice_text(&self.text, pos, len as isize, "")
+            .unwrap();
+    }
+
+    fn get_text(&mut self) -> Box<str> {
+        self.doc.text(&self.text).unwrap().into_boxed_str()
+    }
+
+    fn list_insert(&mut self, pos: usize, num: i32) {
+        self.doc.insert(&self.list, pos, num as i64).unwrap();
+    }
+
+    fn list_del(&mut self, pos: usize, len: usize) {
+        for _ in 0..len {
+            self.doc.delete(&self.list, pos).unwrap();
+        }
+    }
+
+    fn get_list(&mut self) -> Vec<i32> {
+        (0..self.doc.length(&self.list))
+            .map(|i| match self.doc.get(&self.list, i).unwrap() {
+                Some((automerge::Value::Scalar(v), _)) => match v.into_owned() {
+                    ScalarValue::Int(n) => n as i32,
+                    _ => 0,
+                },
+                _ => 0,
+            })
+            .collect()
+    }
+
+    fn map_insert(&mut self, key: &str, num: i32) -> Result<(), Unsupported> {
+        self.doc.put(&self.map, key, num as i64).unwrap();
+        Ok(())
+    }
+
+    fn map_del(&mut self, key: &str) -> Result<(), Unsupported> {
+        self.doc.delete(&self.map, key).unwrap();
+        Ok(())
+    }
+
+    fn get_map(&mut self) -> Result<HashMap<String, i32>, Unsupported> {
+        Ok(self
+            .doc
+            .map_range(&self.map, ..)
+            .filter_map(|(key, value, _)| match value {
+                automerge::Value::Scalar(v) => match v.into_owned() {
+                    ScalarValue::Int(n) => Some((key.to_string(), n as i32)),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect())
+    }
+
+    fn mark(&mut self, range: Range<usize>, key: &str, value: MarkValue) -> Result<(), Unsupported> {
+        let mark = Mark::new(key.to_string(), mark_value_to_scalar(&value), range.start, range.end);
+        self.doc
+            .mark(&self.text, mark, ExpandMark::None)
+            .unwrap();
+        Ok(())
+    }
+
+    fn unmark(&mut self, range: Range<usize>, key: &str) -> Result<(), Unsupported> {
+        self.doc
+            .unmark(&self.text, key, range.start, range.end, ExpandMark::None)
+            .unwrap();
+        Ok(())
+    }
+
+    fn get_marks(&mut self) -> Result<Vec<MarkSpan>, Unsupported> {
+        let marks = self.doc.marks(&self.text).unwrap();
+        Ok(marks
+            .into_iter()
+            .flat_map(|mark: Mark| {
+                let set: &MarkSet = mark.data();
+                set.iter()
+                    .filter_map(|(key, value)| {
+                        scalar_to_mark_value(value).map(|value| MarkSpan {
+                            start: mark.start,
+                            end: mark.end,
+                            key: key.to_string(),
+                            value,
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect())
+    }
+
+    fn encode_full(&mut self) -> Vec<u8> {
+        self.doc.save()
+    }
+
+    fn decode_full(&mut self, update: &[u8]) {
+        self.doc.load_incremental(update).unwrap();
+    }
+
+    fn encode_delta(&self, since: &Self::Version) -> Vec<u8> {
+        self.doc
+            .get_changes(since)
+            .into_iter()
+            .flat_map(|change| change.raw_bytes().to_vec())
+            .collect()
+    }
+
+    fn apply_delta(&mut self, data: &[u8]) {
+        self.doc.load_incremental(data).unwrap();
+    }
+
+    fn merge(&mut self, other: &mut Self) -> u64 {
+        let since = self.version();
+        let other_since = other.version();
+        let a_to_b = self.encode_delta(&other_since);
+        let b_to_a = other.encode_delta(&since);
+        self.apply_delta(&b_to_a);
+        other.apply_delta(&a_to_b);
+        (a_to_b.len() + b_to_a.len()) as u64
+    }
+
+    fn version(&self) -> Self::Version {
+        self.doc.get_heads()
+    }
+}
@@ -0,0 +1,186 @@
+use diamond_types::list::{
+    encoding::{EncodeOptions, ENCODE_FULL},
+    remote_ids::RemoteId,
+    ListCRDT,
+};
+use rand::Rng;
+use std::{collections::HashMap, ops::Range};
+
+use crate::{Crdt, MarkSpan, MarkValue, Unsupported};
+
+/// The meet of two version vectors: for every agent both sides have seen,
+/// the lowest sequence number they agree on; agents only one side has seen
+/// contribute nothing, since the common ancestor predates them entirely.
+/// This is the version each side should diff its ops against when computing
+/// a delta, since it's the newest point both replicas are guaranteed to share.
+fn common_frontier(a: &[RemoteId], b: &[RemoteId]) -> Vec<RemoteId> {
+    let b_seqs: HashMap<&str, usize> = b.iter().map(|r| (r.agent.as_str(), r.seq)).collect();
+    a.iter()
+        .filter_map(|r| {
+            b_seqs.get(r.agent.as_str()).map(|&b_seq| RemoteId {
+                agent: r.agent.clone(),
+                seq: r.seq.min(b_seq),
+            })
+        })
+        .collect()
+}
+
+/// The diamond-types backend shared by every report and bench target.
+/// `benches/diamond-type.rs` imports this directly rather than keeping its
+/// own copy — criterion bench targets can depend on the lib crate fine.
+pub struct DiamondTypeDoc {
+    doc: ListCRDT,
+    id: String,
+}
+
+impl Crdt for DiamondTypeDoc {
+    type Version = Vec<RemoteId>;
+
+    fn name() -> &'static str {
+        "diamond-type"
+    }
+
+    fn create(_gc: bool, _compression: bool) -> Self {
+        let mut doc = ListCRDT::new();
+        let id: u64 = rand::thread_rng().gen();
+        let _ = doc.get_or_create_agent_id(&id.to_string());
+        DiamondTypeDoc {
+            doc,
+            id: id.to_string(),
+        }
+    }
+
+    fn gc(&self) -> Result<bool, bool> {
+        Err(false)
+    }
+
+    fn compression(&self) -> Result<bool, bool> {
+        Err(false)
+    }
+
+    fn text_insert(&mut self, pos: usize, text: &str) {
+        self.doc.insert(0, pos, text);
+    }
+
+    fn text_del(&mut self, pos: usize, len: usize) {
+        self.doc.delete(0, pos..len + pos);
+    }
+
+    fn get_text(&mut self) -> Box<str> {
+        self.doc.branch.content().to_string().into_boxed_str()
+    }
+
+    fn list_insert(&mut self, pos: usize, _num: i32) {
+        self.doc.insert(0, pos, "0");
+    }
+
+    fn list_del(&mut self, pos: usize, len: usize) {
+        self.doc.delete(0, pos..pos + len);
+    }
+
+    fn get_list(&mut self) -> Vec<i32> {
+        todo!()
+    }
+
+    fn map_insert(&mut self, _key: &str, _num: i32) -> Result<(), Unsupported> {
+        // diamond-types' list CRDT has no registered map/MV-register type.
+        Err(Unsupported)
+    }
+
+    fn map_del(&mut self, _key: &str) -> Result<(), Unsupported> {
+        Err(Unsupported)
+    }
+
+    fn get_map(&mut self) -> Result<HashMap<String, i32>, Unsupported> {
+        Err(Unsupported)
+    }
+
+    fn mark(&mut self, _range: Range<usize>, _key: &str, _value: MarkValue) -> Result<(), Unsupported> {
+        // diamond-types' list CRDT has no rich-text span API.
+        Err(Unsupported)
+    }
+
+    fn unmark(&mut self, _range: Range<usize>, _key: &str) -> Result<(), Unsupported> {
+        Err(Unsupported)
+    }
+
+    fn get_marks(&mut self) -> Result<Vec<MarkSpan>, Unsupported> {
+        Err(Unsupported)
+    }
+
+    fn encode_full(&mut self) -> Vec<u8> {
+        self.doc.oplog.encode(ENCODE_FULL)
+    }
+
+    fn decode_full(&mut self, update: &[u8]) {
+        self.doc.oplog.decode_and_add(update).unwrap();
+        self.doc
+            .branch
+            .merge(&self.doc.oplog, self.doc.oplog.local_version_ref())
+    }
+
+    fn encode_delta(&self, since: &Self::Version) -> Vec<u8> {
+        let from_version = self
+            .doc
+            .oplog
+            .try_remote_to_local_frontier(since.iter().cloned())
+            .unwrap_or_default();
+        self.doc
+            .oplog
+            .encode_from(EncodeOptions::default(), &from_version)
+    }
+
+    fn apply_delta(&mut self, data: &[u8]) {
+        self.decode_full(data);
+    }
+
+    fn merge(&mut self, other: &mut Self) -> u64 {
+        // `encode_from` requires the given version to be contained by the
+        // local version, which doesn't hold under concurrent edits. Diff
+        // each side against the meet of both version vectors instead, so
+        // both deltas are valid regardless of what the other site has done.
+        let since = common_frontier(&self.version(), &other.version());
+        let a_to_b = self.encode_delta(&since);
+        let b_to_a = other.encode_delta(&since);
+        self.apply_delta(&b_to_a);
+        other.apply_delta(&a_to_b);
+        (a_to_b.len() + b_to_a.len()) as u64
+    }
+
+    fn version(&self) -> Self::Version {
+        self.doc.oplog.remote_version().into_vec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn remote_id(agent: &str, seq: usize) -> RemoteId {
+        RemoteId {
+            agent: agent.into(),
+            seq,
+        }
+    }
+
+    fn as_pairs(ids: &[RemoteId]) -> Vec<(&str, usize)> {
+        ids.iter().map(|r| (r.agent.as_str(), r.seq)).collect()
+    }
+
+    #[test]
+    fn common_frontier_takes_the_lower_seq_for_shared_agents() {
+        let a = vec![remote_id("alice", 5), remote_id("bob", 2)];
+        let b = vec![remote_id("alice", 3), remote_id("bob", 4)];
+        assert_eq!(
+            as_pairs(&common_frontier(&a, &b)),
+            vec![("alice", 3), ("bob", 2)]
+        );
+    }
+
+    #[test]
+    fn common_frontier_drops_agents_only_one_side_has_seen() {
+        let a = vec![remote_id("alice", 5), remote_id("carol", 1)];
+        let b = vec![remote_id("alice", 3), remote_id("bob", 4)];
+        assert_eq!(as_pairs(&common_frontier(&a, &b)), vec![("alice", 3)]);
+    }
+}
@@ -1,20 +1,111 @@
 use indicatif::{ProgressBar, ProgressState, ProgressStyle};
-use rand::{rngs::StdRng, Rng, SeedableRng};
-use std::{collections::HashMap, fmt::Write};
+use std::{collections::HashMap, fmt::Write as _, time::Instant};
 
 use crate::{
-    automerge::get_automerge_actions, merge, AutomergeDoc, Crdt, DiamondTypeDoc, LoroDoc, YrsDoc,
+    chunking::{self, ChunkerConfig},
+    merge,
+    workload::{self, Metric, Op, Workload, WorkloadResult},
+    AutomergeDoc, Crdt, DiamondTypeDoc, LoroDoc, YrsDoc,
 };
 
 pub struct DocSizeReport {
     name: String,
-    dataset_name: String,
+    workload_name: String,
     gc: Result<bool, bool>,
     compression: Result<bool, bool>,
     doc_size: Option<usize>,
+    delta_bytes: Option<u64>,
+    wall_time_ms: Option<u128>,
+    /// Bytes of doc size attributable to rich-text formatting metadata
+    /// (marks) rather than content. `None` when the workload has no mark
+    /// ops, since there's nothing to diff against.
+    formatting_bytes: Option<usize>,
+    /// Whether both sites agree on every map key once merged. `None` for the
+    /// single-site mode (nothing to compare) or a backend with no registered
+    /// map/MV-register type.
+    converged: Option<bool>,
 }
 
-fn gen_report<C: Crdt>(gc: bool, compression: bool) -> DocSizeReport {
+/// Whether `workload`'s schedule has a merge step that fires right after op
+/// index `current`, i.e. a step whose `at` equals `current`. Keying off the
+/// op index (rather than the schedule entry's own position) means a schedule
+/// with fewer entries than there are ops still fires at the right points.
+fn schedules_merge(workload: &Workload, current: usize) -> bool {
+    workload
+        .merge_schedule
+        .iter()
+        .any(|step| step.at == current)
+}
+
+pub(crate) fn apply_op<C: Crdt>(crdt: &mut C, op: &Op) {
+    match op {
+        Op::TextInsert { pos, text } => crdt.text_insert(*pos, text),
+        Op::TextDel { pos, len } => crdt.text_del(*pos, *len),
+        Op::ListInsert { pos, value } => crdt.list_insert(*pos, *value),
+        Op::ListDel { pos, len } => crdt.list_del(*pos, *len),
+        // Backends with no registered map/MV-register type report Unsupported;
+        // the workload still replays so non-map metrics stay comparable.
+        Op::MapInsert { key, value } => {
+            let _ = crdt.map_insert(key, *value);
+        }
+        Op::MapDel { key } => {
+            let _ = crdt.map_del(key);
+        }
+        // Backends without a rich-text span API report Unsupported; the
+        // workload still replays so non-mark metrics stay comparable.
+        Op::Mark { start, end, key, value } => {
+            let _ = crdt.mark(*start..*end, key, value.clone());
+        }
+        Op::Unmark { start, end, key } => {
+            let _ = crdt.unmark(*start..*end, key);
+        }
+    }
+}
+
+/// Whether an op contributes to rich-text formatting rather than content.
+fn is_mark_op(op: &Op) -> bool {
+    matches!(op, Op::Mark { .. } | Op::Unmark { .. })
+}
+
+/// Replays `workload` with and without its mark ops and diffs the encoded
+/// sizes, so the gap can be attributed to rich-text formatting metadata
+/// rather than content. `None` if the workload has no mark ops at all.
+fn formatting_bytes<C: Crdt>(workload: &Workload) -> Option<usize> {
+    if !workload.ops.iter().any(|site_op| is_mark_op(&site_op.op)) {
+        return None;
+    }
+
+    let mut with_marks = C::create(false, false);
+    for site_op in &workload.ops {
+        apply_op(&mut with_marks, &site_op.op);
+    }
+    let doc_size_with_marks = with_marks.encode_full().len();
+
+    let mut without_marks = C::create(false, false);
+    for site_op in workload.ops.iter().filter(|site_op| !is_mark_op(&site_op.op)) {
+        apply_op(&mut without_marks, &site_op.op);
+    }
+    let doc_size_without_marks = without_marks.encode_full().len();
+
+    Some(doc_size_with_marks.saturating_sub(doc_size_without_marks))
+}
+
+fn progress_bar(total_len: u64) -> ProgressBar {
+    let bar = ProgressBar::new(total_len);
+    bar.set_style(
+        ProgressStyle::with_template(
+            "{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {pos:>7}/{len:7} ({eta})",
+        )
+        .unwrap()
+        .with_key("eta", |state: &ProgressState, w: &mut dyn Write| {
+            write!(w, "{:.1}s", state.eta().as_secs_f64()).unwrap()
+        })
+        .progress_chars("#>-"),
+    );
+    bar
+}
+
+fn gen_report<C: Crdt>(workload: &Workload, gc: bool, compression: bool) -> DocSizeReport {
     let mut crdt = C::create(gc, compression);
     let mut run = true;
     if let Err(support_gc) = crdt.gc() {
@@ -27,60 +118,147 @@ fn gen_report<C: Crdt>(gc: bool, compression: bool) -> DocSizeReport {
     if !run {
         return DocSizeReport {
             name: C::name().to_string(),
-            dataset_name: "automerge paper".to_string(),
+            workload_name: workload.name.clone(),
             gc: crdt.gc(),
             compression: crdt.compression(),
             doc_size: None,
+            delta_bytes: None,
+            wall_time_ms: None,
+            formatting_bytes: None,
+            converged: None,
         };
     }
-    let actions = get_automerge_actions();
-    let total_len = actions.len() as u64;
-    let bar = ProgressBar::new(total_len);
-    bar.set_style(
-        ProgressStyle::with_template(
-            "{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {pos:>7}/{len:7} ({eta})",
-        )
-        .unwrap()
-        .with_key("eta", |state: &ProgressState, w: &mut dyn Write| {
-            write!(w, "{:.1}s", state.eta().as_secs_f64()).unwrap()
-        })
-        .progress_chars("#>-"),
+
+    let total_len = workload.ops.len() as u64;
+    let bar = progress_bar(total_len);
+    let start = Instant::now();
+    for (current, site_op) in workload.ops.iter().enumerate() {
+        if current % 100 == 0 {
+            bar.set_position(current as u64);
+        }
+        apply_op(&mut crdt, &site_op.op);
+    }
+    let encoded = crdt.encode_full();
+    let elapsed = start.elapsed();
+    bar.set_position(total_len);
+    bar.finish();
+    println!(
+        "{} workload {} gc {} compression {} doc_size {}",
+        C::name(),
+        workload.name,
+        crdt.gc().map_or("x".to_string(), |v| v.to_string()),
+        crdt.compression()
+            .map_or("x".to_string(), |v| v.to_string()),
+        encoded.len(),
     );
-    for (current, action) in actions.iter().enumerate() {
+    DocSizeReport {
+        name: C::name().to_string(),
+        workload_name: workload.name.clone(),
+        gc: crdt.gc(),
+        compression: crdt.compression(),
+        doc_size: workload.metrics.contains(&Metric::DocSize).then_some(encoded.len()),
+        delta_bytes: None,
+        wall_time_ms: workload
+            .metrics
+            .contains(&Metric::WallTime)
+            .then_some(elapsed.as_millis()),
+        formatting_bytes: formatting_bytes::<C>(workload),
+        converged: None,
+    }
+}
+
+/// Whether both sites agree on every map key, or `None` if this backend has
+/// no registered map/MV-register type.
+fn map_converged<C: Crdt>(a: &mut C, b: &mut C) -> Option<bool> {
+    match (a.get_map(), b.get_map()) {
+        (Ok(map_a), Ok(map_b)) => Some(map_a == map_b),
+        _ => None,
+    }
+}
+
+fn gen_report_parallel<C: Crdt>(workload: &Workload, gc: bool, compression: bool) -> DocSizeReport {
+    assert_eq!(workload.sites, 2, "two-site replay needs exactly 2 sites, got {}", workload.sites);
+    let mut crdt = C::create(gc, compression);
+    let mut crdt2 = C::create(gc, compression);
+    let mut run = true;
+    if let Err(support_gc) = crdt.gc() {
+        run = support_gc == gc;
+    }
+    if let Err(support_compression) = crdt.compression() {
+        run = support_compression == compression;
+    }
+
+    if !run {
+        return DocSizeReport {
+            name: C::name().to_string(),
+            workload_name: workload.name.clone(),
+            gc: crdt.gc(),
+            compression: crdt.compression(),
+            doc_size: None,
+            delta_bytes: None,
+            wall_time_ms: None,
+            formatting_bytes: None,
+            converged: None,
+        };
+    }
+
+    // With no explicit schedule, sync after every op, matching the old
+    // "merge every batch" behavior. A non-empty schedule merges only at the
+    // points it names, in workload order. Either way a final merge follows
+    // the last op.
+    let merge_every_op = workload.merge_schedule.is_empty();
+    let total_len = workload.ops.len() as u64;
+    let bar = progress_bar(total_len);
+    let start = Instant::now();
+    for (current, site_op) in workload.ops.iter().enumerate() {
         if current % 100 == 0 {
             bar.set_position(current as u64);
         }
-        if action.del != 0 {
-            crdt.text_del(action.pos, action.del);
+        match site_op.site % workload.sites {
+            0 => apply_op(&mut crdt, &site_op.op),
+            _ => apply_op(&mut crdt2, &site_op.op),
         }
 
-        if !action.ins.is_empty() {
-            crdt.text_insert(action.pos, &action.ins);
+        if merge_every_op || schedules_merge(workload, current) {
+            merge(&mut crdt, &mut crdt2);
         }
     }
+    merge(&mut crdt, &mut crdt2);
+    let converged = map_converged(&mut crdt, &mut crdt2);
     let encoded = crdt.encode_full();
+    let elapsed = start.elapsed();
     bar.set_position(total_len);
     bar.finish();
     println!(
-        "{} gc {} compression {} doc_size {}",
+        "{} workload {} gc {} compression {} doc_size {}",
         C::name(),
+        workload.name,
         crdt.gc().map_or("x".to_string(), |v| v.to_string()),
         crdt.compression()
             .map_or("x".to_string(), |v| v.to_string()),
-        Some(encoded.len())
-            .map(|s| s.to_string())
-            .unwrap_or_else(|| "x".to_string()),
+        encoded.len(),
     );
     DocSizeReport {
         name: C::name().to_string(),
-        dataset_name: "automerge paper".to_string(),
+        workload_name: workload.name.clone(),
         gc: crdt.gc(),
         compression: crdt.compression(),
-        doc_size: Some(encoded.len()),
+        doc_size: workload.metrics.contains(&Metric::DocSize).then_some(encoded.len()),
+        delta_bytes: None,
+        wall_time_ms: workload
+            .metrics
+            .contains(&Metric::WallTime)
+            .then_some(elapsed.as_millis()),
+        formatting_bytes: formatting_bytes::<C>(workload),
+        converged,
     }
 }
 
-fn gen_report_parallel<C: Crdt>(gc: bool, compression: bool) -> DocSizeReport {
+/// Same replay as [`gen_report_parallel`], but reports the total number of
+/// delta bytes exchanged across every merge instead of only the final
+/// snapshot size, so the table reflects realistic incremental-sync cost.
+fn gen_report_delta<C: Crdt>(workload: &Workload, gc: bool, compression: bool) -> DocSizeReport {
+    assert_eq!(workload.sites, 2, "two-site replay needs exactly 2 sites, got {}", workload.sites);
     let mut crdt = C::create(gc, compression);
     let mut crdt2 = C::create(gc, compression);
     let mut run = true;
@@ -94,76 +272,67 @@ fn gen_report_parallel<C: Crdt>(gc: bool, compression: bool) -> DocSizeReport {
     if !run {
         return DocSizeReport {
             name: C::name().to_string(),
-            dataset_name: "automerge paper".to_string(),
+            workload_name: workload.name.clone(),
             gc: crdt.gc(),
             compression: crdt.compression(),
             doc_size: None,
+            delta_bytes: None,
+            wall_time_ms: None,
+            formatting_bytes: None,
+            converged: None,
         };
     }
-    let mut rng: StdRng = SeedableRng::seed_from_u64(1);
 
-    let mut actions = get_automerge_actions().into_iter();
-    let total_len = actions.len() as u64;
-    let mut current = 0;
-    let bar = ProgressBar::new(total_len);
-    bar.set_style(
-        ProgressStyle::with_template(
-            "{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {pos:>7}/{len:7} ({eta})",
-        )
-        .unwrap()
-        .with_key("eta", |state: &ProgressState, w: &mut dyn Write| {
-            write!(w, "{:.1}s", state.eta().as_secs_f64()).unwrap()
-        })
-        .progress_chars("#>-"),
-    );
-    while let Some(action) = actions.next() {
+    let merge_every_op = workload.merge_schedule.is_empty();
+    let total_len = workload.ops.len() as u64;
+    let bar = progress_bar(total_len);
+    let start = Instant::now();
+    let mut delta_bytes = 0u64;
+    for (current, site_op) in workload.ops.iter().enumerate() {
         if current % 100 == 0 {
-            bar.set_position(current);
-        }
-        current += 1;
-        if action.del != 0 {
-            crdt.text_del(action.pos, action.del);
-        }
-
-        if !action.ins.is_empty() {
-            crdt.text_insert(action.pos, &action.ins);
-        }
-        merge(&mut crdt, &mut crdt2);
-        let r = rng.gen_range(1..11);
-        for _ in 0..r {
-            if let Some(action) = actions.next() {
-                current += 1;
-                if action.del != 0 {
-                    crdt2.text_del(action.pos, action.del);
-                }
-                if !action.ins.is_empty() {
-                    crdt2.text_insert(action.pos, &action.ins);
-                }
-            } else {
-                break;
-            }
+            bar.set_position(current as u64);
+        }
+        match site_op.site % workload.sites {
+            0 => apply_op(&mut crdt, &site_op.op),
+            _ => apply_op(&mut crdt2, &site_op.op),
+        }
+
+        if merge_every_op || schedules_merge(workload, current) {
+            delta_bytes += merge(&mut crdt, &mut crdt2);
         }
-        merge(&mut crdt, &mut crdt2);
     }
+    delta_bytes += merge(&mut crdt, &mut crdt2);
+    let converged = map_converged(&mut crdt, &mut crdt2);
     let encoded = crdt.encode_full();
+    let elapsed = start.elapsed();
     bar.set_position(total_len);
     bar.finish();
     println!(
-        "{} gc {} compression {} doc_size {}",
+        "{} workload {} gc {} compression {} doc_size {} delta_bytes {}",
         C::name(),
+        workload.name,
         crdt.gc().map_or("x".to_string(), |v| v.to_string()),
         crdt.compression()
             .map_or("x".to_string(), |v| v.to_string()),
-        Some(encoded.len())
-            .map(|s| s.to_string())
-            .unwrap_or_else(|| "x".to_string()),
+        encoded.len(),
+        delta_bytes,
     );
     DocSizeReport {
         name: C::name().to_string(),
-        dataset_name: "automerge paper".to_string(),
+        workload_name: workload.name.clone(),
         gc: crdt.gc(),
         compression: crdt.compression(),
-        doc_size: Some(encoded.len()),
+        doc_size: workload.metrics.contains(&Metric::DocSize).then_some(encoded.len()),
+        delta_bytes: workload
+            .metrics
+            .contains(&Metric::MergeBytes)
+            .then_some(delta_bytes),
+        wall_time_ms: workload
+            .metrics
+            .contains(&Metric::WallTime)
+            .then_some(elapsed.as_millis()),
+        formatting_bytes: formatting_bytes::<C>(workload),
+        converged,
     }
 }
 
@@ -187,12 +356,13 @@ impl ReportTable {
         let automerge = self.0.get(AutomergeDoc::name()).unwrap();
         let diamond_type = self.0.get(DiamondTypeDoc::name()).unwrap();
         let yrs = self.0.get(YrsDoc::name()).unwrap();
+        let backends = [loro, automerge, diamond_type, yrs];
+
         md.push_str("|     |  loro  | automerge | diamond-type | yrs |\n");
         md.push_str("|  ----  | ----  |  ----  | ----  |  ----  |");
-
         for (title, index) in [("", 0), ("gc", 1), ("compress", 2), ("gc & compress", 3)] {
             md.push_str(&format!("\n|{}|", title));
-            for crdt in [loro, diamond_type, yrs] {
+            for crdt in backends {
                 let size = crdt[index]
                     .doc_size
                     .map(|s| s.to_string())
@@ -201,38 +371,309 @@ impl ReportTable {
             }
         }
         md.push('\n');
+
+        md.push_str("\n| formatting bytes |  loro  | automerge | diamond-type | yrs |\n");
+        md.push_str("|  ----  | ----  |  ----  | ----  |  ----  |");
+        for (title, index) in [("", 0), ("gc", 1), ("compress", 2), ("gc & compress", 3)] {
+            md.push_str(&format!("\n|{}|", title));
+            for crdt in backends {
+                let bytes = crdt[index]
+                    .formatting_bytes
+                    .map(|b| b.to_string())
+                    .unwrap_or_else(|| "x".to_string());
+                md.push_str(&format!(" {} |", bytes))
+            }
+        }
+        md.push('\n');
+
+        md.push_str("\n| map converged |  loro  | automerge | diamond-type | yrs |\n");
+        md.push_str("|  ----  | ----  |  ----  | ----  |  ----  |");
+        for (title, index) in [("", 0), ("gc", 1), ("compress", 2), ("gc & compress", 3)] {
+            md.push_str(&format!("\n|{}|", title));
+            for crdt in backends {
+                let converged = crdt[index]
+                    .converged
+                    .map_or("x".to_string(), |c| c.to_string());
+                md.push_str(&format!(" {} |", converged))
+            }
+        }
+        md.push('\n');
         md
     }
 
-    // fn to_crdt_md<C: Crdt>(&self) -> String {}
+    fn to_results(&self) -> Vec<WorkloadResult> {
+        self.0
+            .values()
+            .flatten()
+            .map(|report| WorkloadResult {
+                workload: report.workload_name.clone(),
+                backend: report.name.clone(),
+                gc: report.gc.unwrap_or(false),
+                compression: report.compression.unwrap_or(false),
+                doc_size: report.doc_size,
+                merge_bytes: report.delta_bytes,
+                wall_time_ms: report.wall_time_ms,
+                converged: report.converged,
+                peak_doc_size: None,
+                formatting_bytes: report.formatting_bytes,
+                unique_chunk_bytes: None,
+            })
+            .collect()
+    }
+}
+
+/// How to replay a workload's ops: on one site, across two sites, or across
+/// two sites while also recording the delta-sync bytes their merges exchange.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ReplayMode {
+    Single,
+    Parallel,
+    Delta,
 }
 
-fn per_crdt<C: Crdt>(table: &mut ReportTable, parallel: bool) {
+fn per_crdt<C: Crdt>(table: &mut ReportTable, workload: &Workload, mode: ReplayMode) {
     println!("Benchmarking {}", C::name());
     // TODO: skip if crdt doesn't support gc or compression
     for compression in [false, true] {
         for gc in [false, true] {
-            let report = if parallel {
-                gen_report_parallel::<C>(gc, compression)
-            } else {
-                gen_report::<C>(gc, compression)
+            let report = match mode {
+                ReplayMode::Single => gen_report::<C>(workload, gc, compression),
+                ReplayMode::Parallel => gen_report_parallel::<C>(workload, gc, compression),
+                ReplayMode::Delta => gen_report_delta::<C>(workload, gc, compression),
             };
             table.insert_report::<C>(report);
         }
     }
 }
 
-fn bench_document_size(parallel: bool) -> ReportTable {
-    println!("Benchmarking doc size......");
+fn bench_document_size(workload: &Workload, mode: ReplayMode) -> ReportTable {
+    println!("Benchmarking doc size for workload '{}'......", workload.name);
     let mut report_table = ReportTable::new();
-    per_crdt::<LoroDoc>(&mut report_table, parallel);
-    // per_crdt::<AutomergeDoc>(&mut report_table, parallel);
-    per_crdt::<YrsDoc>(&mut report_table, parallel);
-    per_crdt::<DiamondTypeDoc>(&mut report_table, parallel);
+    per_crdt::<LoroDoc>(&mut report_table, workload, mode);
+    per_crdt::<AutomergeDoc>(&mut report_table, workload, mode);
+    per_crdt::<YrsDoc>(&mut report_table, workload, mode);
+    per_crdt::<DiamondTypeDoc>(&mut report_table, workload, mode);
     report_table
 }
 
-pub fn run_doc_size(parallel: bool) -> String {
-    let table = bench_document_size(parallel);
-    table.to_all_md()
+/// Runs the workload at `workload_path`, writes its results to
+/// `results_path` as JSON, and returns the markdown report. If
+/// `baseline_path` is given and any backend regressed past `threshold`
+/// (e.g. `0.05` for 5%), the regressions are appended to the report so a CI
+/// job can fail the build on the text alone. A formatting-dominant workload
+/// like `rich-text.json` doesn't need a separate entry point: its
+/// `formatting_bytes` shows up in the same table and results file.
+pub fn run_doc_size(
+    workload_path: &str,
+    mode: ReplayMode,
+    results_path: &str,
+    baseline_path: Option<&str>,
+    threshold: f64,
+) -> std::io::Result<String> {
+    let workload = Workload::load(workload_path)?;
+    let table = bench_document_size(&workload, mode);
+    let results = table.to_results();
+    workload::write_results(results_path, &results)?;
+
+    let mut report = table.to_all_md();
+    workload::append_regression_report(&mut report, baseline_path, &results, threshold)?;
+    Ok(report)
+}
+
+/// Replays `workload`, taking an `encode_full` snapshot every `every` ops
+/// (plus a final one), so dedup can be measured against the series of
+/// snapshots a backend would actually accumulate over its history. Skips the
+/// trailing snapshot when the last op already landed on an `every` boundary,
+/// since that snapshot was already taken inside the loop.
+fn collect_snapshots<C: Crdt>(workload: &Workload, every: usize) -> Vec<Vec<u8>> {
+    assert!(every > 0, "snapshot_every must be positive");
+    let mut crdt = C::create(false, false);
+    let mut snapshots = Vec::new();
+    let mut last_op_snapshotted = false;
+    for (i, site_op) in workload.ops.iter().enumerate() {
+        apply_op(&mut crdt, &site_op.op);
+        last_op_snapshotted = (i + 1) % every == 0;
+        if last_op_snapshotted {
+            snapshots.push(crdt.encode_full());
+        }
+    }
+    if !last_op_snapshotted {
+        snapshots.push(crdt.encode_full());
+    }
+    snapshots
+}
+
+/// Runs a workload against every benchmarked backend, chunks the series of
+/// snapshots it produces, writes its results to `results_path` as JSON, and
+/// returns the markdown report. If `baseline_path` is given and any backend's
+/// unique chunk bytes regressed past `threshold` (e.g. `0.05` for 5%), the
+/// regressions are appended to the report.
+pub fn run_dedup_report(
+    workload_path: &str,
+    snapshot_every: usize,
+    results_path: &str,
+    baseline_path: Option<&str>,
+    threshold: f64,
+) -> std::io::Result<String> {
+    let workload = Workload::load(workload_path)?;
+    let config = ChunkerConfig::default();
+    let reports = [
+        chunking::measure_dedup(
+            LoroDoc::name(),
+            &collect_snapshots::<LoroDoc>(&workload, snapshot_every),
+            &config,
+        ),
+        chunking::measure_dedup(
+            AutomergeDoc::name(),
+            &collect_snapshots::<AutomergeDoc>(&workload, snapshot_every),
+            &config,
+        ),
+        chunking::measure_dedup(
+            YrsDoc::name(),
+            &collect_snapshots::<YrsDoc>(&workload, snapshot_every),
+            &config,
+        ),
+        chunking::measure_dedup(
+            DiamondTypeDoc::name(),
+            &collect_snapshots::<DiamondTypeDoc>(&workload, snapshot_every),
+            &config,
+        ),
+    ];
+
+    let results: Vec<WorkloadResult> = reports
+        .iter()
+        .map(|report| WorkloadResult {
+            workload: workload.name.clone(),
+            backend: report.name.clone(),
+            gc: false,
+            compression: false,
+            doc_size: None,
+            merge_bytes: None,
+            wall_time_ms: None,
+            converged: None,
+            peak_doc_size: None,
+            formatting_bytes: None,
+            unique_chunk_bytes: Some(report.unique_chunk_bytes),
+        })
+        .collect();
+    workload::write_results(results_path, &results)?;
+
+    let mut md = String::new();
+    md.push_str("| backend | snapshots | summed bytes | unique chunk bytes |\n");
+    md.push_str("| ---- | ---- | ---- | ---- |\n");
+    for report in &reports {
+        let _ = writeln!(
+            md,
+            "| {} | {} | {} | {} |",
+            report.name, report.snapshot_count, report.summed_snapshot_bytes, report.unique_chunk_bytes
+        );
+    }
+
+    workload::append_regression_report(&mut md, baseline_path, &results, threshold)?;
+    Ok(md)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Counter(usize);
+
+    impl Crdt for Counter {
+        type Version = ();
+        fn name() -> &'static str {
+            "counter"
+        }
+        fn create(_gc: bool, _compression: bool) -> Self {
+            Counter(0)
+        }
+        fn gc(&self) -> Result<bool, bool> {
+            Err(false)
+        }
+        fn compression(&self) -> Result<bool, bool> {
+            Err(false)
+        }
+        fn text_insert(&mut self, _pos: usize, _text: &str) {
+            self.0 += 1;
+        }
+        fn text_del(&mut self, _pos: usize, _len: usize) {
+            self.0 += 1;
+        }
+        fn get_text(&mut self) -> Box<str> {
+            "".into()
+        }
+        fn list_insert(&mut self, _pos: usize, _num: i32) {}
+        fn list_del(&mut self, _pos: usize, _len: usize) {}
+        fn get_list(&mut self) -> Vec<i32> {
+            Vec::new()
+        }
+        fn map_insert(&mut self, _key: &str, _num: i32) -> Result<(), crate::Unsupported> {
+            Err(crate::Unsupported)
+        }
+        fn map_del(&mut self, _key: &str) -> Result<(), crate::Unsupported> {
+            Err(crate::Unsupported)
+        }
+        fn get_map(&mut self) -> Result<HashMap<String, i32>, crate::Unsupported> {
+            Err(crate::Unsupported)
+        }
+        fn mark(
+            &mut self,
+            _range: std::ops::Range<usize>,
+            _key: &str,
+            _value: crate::MarkValue,
+        ) -> Result<(), crate::Unsupported> {
+            Err(crate::Unsupported)
+        }
+        fn unmark(
+            &mut self,
+            _range: std::ops::Range<usize>,
+            _key: &str,
+        ) -> Result<(), crate::Unsupported> {
+            Err(crate::Unsupported)
+        }
+        fn get_marks(&mut self) -> Result<Vec<crate::MarkSpan>, crate::Unsupported> {
+            Err(crate::Unsupported)
+        }
+        fn encode_full(&mut self) -> Vec<u8> {
+            vec![self.0 as u8; self.0]
+        }
+        fn decode_full(&mut self, _update: &[u8]) {}
+        fn encode_delta(&self, _since: &Self::Version) -> Vec<u8> {
+            Vec::new()
+        }
+        fn apply_delta(&mut self, _data: &[u8]) {}
+        fn merge(&mut self, _other: &mut Self) -> u64 {
+            0
+        }
+        fn version(&self) {}
+    }
+
+    fn workload_of(op_count: usize) -> Workload {
+        Workload {
+            name: "test".into(),
+            sites: 1,
+            ops: (0..op_count)
+                .map(|_| workload::SiteOp {
+                    site: 0,
+                    op: Op::TextInsert { pos: 0, text: "x".into() },
+                })
+                .collect(),
+            merge_schedule: Vec::new(),
+            metrics: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn collect_snapshots_does_not_double_count_a_boundary_op() {
+        let workload = workload_of(4);
+        let snapshots = collect_snapshots::<Counter>(&workload, 2);
+        assert_eq!(snapshots, vec![vec![2, 2], vec![4, 4, 4, 4]]);
+    }
+
+    #[test]
+    fn collect_snapshots_adds_a_trailing_snapshot_off_boundary() {
+        let workload = workload_of(3);
+        let snapshots = collect_snapshots::<Counter>(&workload, 2);
+        assert_eq!(snapshots, vec![vec![2, 2], vec![3, 3, 3]]);
+    }
 }
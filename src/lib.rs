@@ -0,0 +1,111 @@
+use std::{collections::HashMap, ops::Range};
+
+pub mod chunking;
+pub mod doc_size;
+pub mod multisite;
+pub mod workload;
+
+mod automerge;
+mod diamond_type;
+mod loro;
+mod yrs;
+
+pub use automerge::AutomergeDoc;
+pub use diamond_type::DiamondTypeDoc;
+pub use loro::LoroDoc;
+pub use yrs::YrsDoc;
+pub use workload::Workload;
+
+/// Returned by a capability probe (`mark`/`unmark`/`get_marks`, alongside the
+/// existing `gc`/`compression`) for a backend that doesn't support the
+/// feature at all, so callers can skip it instead of panicking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unsupported;
+
+/// The value a rich-text mark carries, e.g. `bold: true` or `link: "https://..."`.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(untagged)]
+pub enum MarkValue {
+    Bool(bool),
+    Str(String),
+}
+
+/// One formatting span as reported back by `get_marks`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarkSpan {
+    pub start: usize,
+    pub end: usize,
+    pub key: String,
+    pub value: MarkValue,
+}
+
+/// Common surface every benchmarked CRDT backend implements so the report
+/// generators in [`doc_size`] can drive them uniformly.
+pub trait Crdt: Sized {
+    type Version: Clone;
+
+    fn name() -> &'static str;
+    fn create(gc: bool, compression: bool) -> Self;
+
+    /// `Ok(gc)` once the backend actually honored the flag it was created with,
+    /// `Err(actual)` if the backend doesn't support toggling it and always runs
+    /// with `actual` instead.
+    fn gc(&self) -> Result<bool, bool>;
+    fn compression(&self) -> Result<bool, bool>;
+
+    fn text_insert(&mut self, pos: usize, text: &str);
+    fn text_del(&mut self, pos: usize, len: usize);
+    fn get_text(&mut self) -> Box<str>;
+
+    fn list_insert(&mut self, pos: usize, num: i32);
+    fn list_del(&mut self, pos: usize, len: usize);
+    fn get_list(&mut self) -> Vec<i32>;
+
+    /// `Err(Unsupported)` for backends with no registered map/MV-register type.
+    fn map_insert(&mut self, key: &str, num: i32) -> Result<(), Unsupported>;
+    fn map_del(&mut self, key: &str) -> Result<(), Unsupported>;
+    fn get_map(&mut self) -> Result<HashMap<String, i32>, Unsupported>;
+
+    /// Applies a rich-text mark (e.g. bold, italic, a link) over `range`.
+    /// `Err(Unsupported)` for backends with no rich-text span API.
+    fn mark(&mut self, range: Range<usize>, key: &str, value: MarkValue) -> Result<(), Unsupported>;
+    fn unmark(&mut self, range: Range<usize>, key: &str) -> Result<(), Unsupported>;
+    fn get_marks(&mut self) -> Result<Vec<MarkSpan>, Unsupported>;
+
+    fn encode_full(&mut self) -> Vec<u8>;
+    fn decode_full(&mut self, update: &[u8]);
+
+    /// Encodes the ops this replica has that `since` doesn't, using the
+    /// backend's native state-vector/version-vector diff.
+    fn encode_delta(&self, since: &Self::Version) -> Vec<u8>;
+    fn apply_delta(&mut self, data: &[u8]);
+
+    /// Merges `other` into `self` and vice versa, returning the total number
+    /// of delta bytes exchanged in both directions so callers can measure
+    /// bytes-on-the-wire instead of just the final snapshot size.
+    fn merge(&mut self, other: &mut Self) -> u64;
+    fn version(&self) -> Self::Version;
+}
+
+/// Merges two replicas of the same document. Thin wrapper so call sites don't
+/// need to spell out `C::merge(a, b)`. Returns the bytes exchanged.
+pub fn merge<C: Crdt>(a: &mut C, b: &mut C) -> u64 {
+    C::merge(a, b)
+}
+
+/// Criterion entry point shared by every `benches/*.rs` binary: replays the
+/// named workload's op stream against `C` and times it.
+pub fn entry<C: Crdt>(workload_path: &str) {
+    let workload = Workload::load(workload_path)
+        .unwrap_or_else(|e| panic!("failed to load workload {workload_path}: {e}"));
+    let mut criterion = criterion::Criterion::default().configure_from_args();
+    criterion.bench_function(&workload.name, |b| {
+        b.iter(|| {
+            let mut crdt = C::create(false, false);
+            for site_op in &workload.ops {
+                doc_size::apply_op(&mut crdt, &site_op.op);
+            }
+        })
+    });
+    criterion.final_summary();
+}
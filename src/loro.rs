@@ -0,0 +1,166 @@
+use loro::{ExportMode, LoroDoc as Inner, LoroValue, VersionVector};
+use std::{collections::HashMap, ops::Range};
+
+use crate::{Crdt, MarkSpan, MarkValue, Unsupported};
+
+fn mark_value_to_loro(value: &MarkValue) -> LoroValue {
+    match value {
+        MarkValue::Bool(b) => LoroValue::Bool(*b),
+        MarkValue::Str(s) => LoroValue::String(s.as_str().into()),
+    }
+}
+
+fn loro_to_mark_value(value: &LoroValue) -> Option<MarkValue> {
+    match value {
+        LoroValue::Bool(b) => Some(MarkValue::Bool(*b)),
+        LoroValue::String(s) => Some(MarkValue::Str(s.to_string())),
+        _ => None,
+    }
+}
+
+pub struct LoroDoc {
+    doc: Inner,
+}
+
+impl Crdt for LoroDoc {
+    type Version = VersionVector;
+
+    fn name() -> &'static str {
+        "loro"
+    }
+
+    fn create(_gc: bool, _compression: bool) -> Self {
+        LoroDoc { doc: Inner::new() }
+    }
+
+    fn gc(&self) -> Result<bool, bool> {
+        Err(false)
+    }
+
+    fn compression(&self) -> Result<bool, bool> {
+        Err(false)
+    }
+
+    fn text_insert(&mut self, pos: usize, text: &str) {
+        self.doc.get_text("text").insert(pos, text).unwrap();
+    }
+
+    fn text_del(&mut self, pos: usize, len: usize) {
+        self.doc.get_text("text").delete(pos, len).unwrap();
+    }
+
+    fn get_text(&mut self) -> Box<str> {
+        self.doc.get_text("text").to_string().into_boxed_str()
+    }
+
+    fn list_insert(&mut self, pos: usize, num: i32) {
+        self.doc.get_list("list").insert(pos, num).unwrap();
+    }
+
+    fn list_del(&mut self, pos: usize, len: usize) {
+        self.doc.get_list("list").delete(pos, len).unwrap();
+    }
+
+    fn get_list(&mut self) -> Vec<i32> {
+        let list = self.doc.get_list("list");
+        (0..list.len())
+            .map(|i| match list.get(i) {
+                Some(LoroValue::I64(n)) => n as i32,
+                _ => 0,
+            })
+            .collect()
+    }
+
+    fn map_insert(&mut self, key: &str, num: i32) -> Result<(), Unsupported> {
+        self.doc.get_map("map").insert(key, num).unwrap();
+        Ok(())
+    }
+
+    fn map_del(&mut self, key: &str) -> Result<(), Unsupported> {
+        self.doc.get_map("map").delete(key).unwrap();
+        Ok(())
+    }
+
+    fn get_map(&mut self) -> Result<HashMap<String, i32>, Unsupported> {
+        let map = self.doc.get_map("map");
+        let mut out = HashMap::new();
+        map.for_each(|key, value| {
+            if let LoroValue::I64(n) = value {
+                out.insert(key.to_string(), n as i32);
+            }
+        });
+        Ok(out)
+    }
+
+    fn mark(&mut self, range: Range<usize>, key: &str, value: MarkValue) -> Result<(), Unsupported> {
+        self.doc
+            .get_text("text")
+            .mark(range, key, mark_value_to_loro(&value))
+            .unwrap();
+        Ok(())
+    }
+
+    fn unmark(&mut self, range: Range<usize>, key: &str) -> Result<(), Unsupported> {
+        self.doc.get_text("text").unmark(range, key).unwrap();
+        Ok(())
+    }
+
+    fn get_marks(&mut self) -> Result<Vec<MarkSpan>, Unsupported> {
+        let mut spans = Vec::new();
+        let mut pos = 0usize;
+        for span in self.doc.get_text("text").get_richtext_value().into_list().unwrap().iter() {
+            let span = span.as_map().unwrap();
+            let len = span
+                .get("insert")
+                .and_then(|v| v.as_string())
+                .map(|s| s.chars().count())
+                .unwrap_or(0);
+            if let Some(attrs) = span.get("attributes").and_then(|v| v.as_map()) {
+                for (key, value) in attrs.iter() {
+                    if let Some(value) = loro_to_mark_value(value) {
+                        spans.push(MarkSpan {
+                            start: pos,
+                            end: pos + len,
+                            key: key.clone(),
+                            value,
+                        });
+                    }
+                }
+            }
+            pos += len;
+        }
+        Ok(spans)
+    }
+
+    fn encode_full(&mut self) -> Vec<u8> {
+        self.doc.export(ExportMode::Snapshot).unwrap()
+    }
+
+    fn decode_full(&mut self, update: &[u8]) {
+        self.doc.import(update).unwrap();
+    }
+
+    fn encode_delta(&self, since: &Self::Version) -> Vec<u8> {
+        self.doc
+            .export(ExportMode::updates_owned(since.clone()))
+            .unwrap()
+    }
+
+    fn apply_delta(&mut self, data: &[u8]) {
+        self.decode_full(data);
+    }
+
+    fn merge(&mut self, other: &mut Self) -> u64 {
+        let a_vv = self.version();
+        let b_vv = other.version();
+        let a_to_b = self.encode_delta(&b_vv);
+        let b_to_a = other.encode_delta(&a_vv);
+        self.apply_delta(&b_to_a);
+        other.apply_delta(&a_to_b);
+        (a_to_b.len() + b_to_a.len()) as u64
+    }
+
+    fn version(&self) -> Self::Version {
+        self.doc.oplog_vv()
+    }
+}
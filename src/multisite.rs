@@ -0,0 +1,238 @@
+//! N-site convergence harness with a randomized, seeded gossip schedule.
+//!
+//! `gen_report_parallel` (and `gen_report_delta`) only ever model two sites,
+//! which never exercises true concurrent edits across more than one pair.
+//! This module keeps `site_count` independent replicas, replays each of a
+//! workload's ops on site `site_op.site % site_count`, and syncs them according to a schedule that occasionally isolates a site
+//! for a few steps before letting it catch up.
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use std::collections::HashMap;
+
+use crate::{
+    doc_size::apply_op,
+    merge,
+    workload::{self, Op, Workload, WorkloadResult},
+    AutomergeDoc, Crdt, DiamondTypeDoc, LoroDoc, YrsDoc,
+};
+
+const ISOLATE_PROBABILITY: f64 = 0.15;
+const ISOLATION_MIN_STEPS: u32 = 2;
+const ISOLATION_MAX_STEPS: u32 = 6;
+
+pub struct ConvergenceReport {
+    pub name: String,
+    pub workload_name: String,
+    pub site_count: usize,
+    pub converged: bool,
+    pub peak_doc_size: usize,
+    pub total_delta_bytes: u64,
+}
+
+/// Checks every dimension a workload actually exercises: text always (every
+/// backend implements it), plus list and/or map when the workload contains
+/// ops for them. A dimension no site touched is skipped rather than compared,
+/// since some backends (e.g. `YrsDoc::get_list`) panic if called on a
+/// dimension they never use.
+fn converges<C: Crdt>(workload: &Workload, sites: &mut [C]) -> bool {
+    let text_reference = sites[0].get_text();
+    let text_converged = sites
+        .iter_mut()
+        .all(|site| site.get_text() == text_reference);
+
+    let list_converged = if workload
+        .ops
+        .iter()
+        .any(|site_op| matches!(site_op.op, Op::ListInsert { .. } | Op::ListDel { .. }))
+    {
+        let list_reference = sites[0].get_list();
+        sites.iter_mut().all(|site| site.get_list() == list_reference)
+    } else {
+        true
+    };
+
+    let map_converged = if workload
+        .ops
+        .iter()
+        .any(|site_op| matches!(site_op.op, Op::MapInsert { .. } | Op::MapDel { .. }))
+    {
+        match sites[0].get_map() {
+            Ok(map_reference) => sites
+                .iter_mut()
+                .all(|site| site.get_map().is_ok_and(|map| map == map_reference)),
+            // No registered map type: nothing to compare, so don't fail the check.
+            Err(_) => true,
+        }
+    } else {
+        true
+    };
+
+    text_converged && list_converged && map_converged
+}
+
+/// Borrows two distinct elements as a pair of mutable references, so both
+/// sides of a merge can be borrowed at once.
+fn pair_mut<C>(sites: &mut [C], a: usize, b: usize) -> (&mut C, &mut C) {
+    assert_ne!(a, b, "a site can't gossip with itself");
+    if a < b {
+        let (left, right) = sites.split_at_mut(b);
+        (&mut left[a], &mut right[0])
+    } else {
+        let (left, right) = sites.split_at_mut(a);
+        (&mut right[0], &mut left[b])
+    }
+}
+
+/// Replays `workload` across `site_count` independent replicas of `C`,
+/// distributing each op to `site_op.site % site_count`, and gossips them
+/// according to a schedule seeded by `seed`: each step either isolates a
+/// random online site for a few steps, or syncs a random pairing of the
+/// sites that are currently online. Once the schedule finishes, every
+/// remaining site is merged pairwise and checked for convergence.
+pub fn run_convergence<C: Crdt>(
+    workload: &Workload,
+    site_count: usize,
+    seed: u64,
+) -> ConvergenceReport {
+    assert!(
+        site_count >= 2,
+        "convergence benchmark needs at least 2 sites"
+    );
+    let mut rng: StdRng = SeedableRng::seed_from_u64(seed);
+    let mut sites: Vec<C> = (0..site_count).map(|_| C::create(false, false)).collect();
+    // Site -> steps remaining before it's back online.
+    let mut isolated: HashMap<usize, u32> = HashMap::new();
+    let mut peak_doc_size = 0;
+    let mut total_delta_bytes = 0u64;
+
+    for site_op in &workload.ops {
+        let target = site_op.site % site_count;
+        apply_op(&mut sites[target], &site_op.op);
+
+        isolated.retain(|_, remaining| {
+            *remaining -= 1;
+            *remaining > 0
+        });
+
+        let mut online: Vec<usize> = (0..site_count)
+            .filter(|s| !isolated.contains_key(s))
+            .collect();
+        if isolated.len() + 1 < site_count && rng.gen_bool(ISOLATE_PROBABILITY) {
+            let candidate = *online.choose(&mut rng).unwrap();
+            let duration = rng.gen_range(ISOLATION_MIN_STEPS..=ISOLATION_MAX_STEPS);
+            isolated.insert(candidate, duration);
+        } else {
+            online.shuffle(&mut rng);
+            for pair in online.chunks_exact(2) {
+                let (a, b) = pair_mut(&mut sites, pair[0], pair[1]);
+                total_delta_bytes += merge(a, b);
+            }
+        }
+
+        for site in &mut sites {
+            peak_doc_size = peak_doc_size.max(site.encode_full().len());
+        }
+    }
+
+    // Let every site catch up so convergence reflects the fully-synced state.
+    for a in 0..site_count {
+        for b in (a + 1)..site_count {
+            let (left, right) = pair_mut(&mut sites, a, b);
+            total_delta_bytes += merge(left, right);
+        }
+    }
+
+    let converged = converges(workload, &mut sites);
+
+    ConvergenceReport {
+        name: C::name().to_string(),
+        workload_name: workload.name.clone(),
+        site_count,
+        converged,
+        peak_doc_size,
+        total_delta_bytes,
+    }
+}
+
+/// Runs `run_convergence` for every benchmarked backend, writes its results
+/// to `results_path` as JSON, and renders the results as a markdown table.
+/// If `baseline_path` is given and any backend regressed past `threshold`
+/// (e.g. `0.05` for 5%), the regressions are appended to the report.
+pub fn run_convergence_report(
+    workload_path: &str,
+    site_count: usize,
+    seed: u64,
+    results_path: &str,
+    baseline_path: Option<&str>,
+    threshold: f64,
+) -> std::io::Result<String> {
+    let workload = Workload::load(workload_path)?;
+    let reports = [
+        run_convergence::<LoroDoc>(&workload, site_count, seed),
+        run_convergence::<AutomergeDoc>(&workload, site_count, seed),
+        run_convergence::<YrsDoc>(&workload, site_count, seed),
+        run_convergence::<DiamondTypeDoc>(&workload, site_count, seed),
+    ];
+
+    let results: Vec<WorkloadResult> = reports
+        .iter()
+        .map(|report| WorkloadResult {
+            workload: report.workload_name.clone(),
+            backend: report.name.clone(),
+            gc: false,
+            compression: false,
+            doc_size: None,
+            merge_bytes: Some(report.total_delta_bytes),
+            wall_time_ms: None,
+            converged: Some(report.converged),
+            peak_doc_size: Some(report.peak_doc_size),
+            formatting_bytes: None,
+            unique_chunk_bytes: None,
+        })
+        .collect();
+    workload::write_results(results_path, &results)?;
+
+    let mut md = String::new();
+    md.push_str("| backend | converged | peak doc size | bytes exchanged |\n");
+    md.push_str("| ---- | ---- | ---- | ---- |\n");
+    for report in &reports {
+        md.push_str(&format!(
+            "| {} | {} | {} | {} |\n",
+            report.name, report.converged, report.peak_doc_size, report.total_delta_bytes
+        ));
+    }
+
+    workload::append_regression_report(&mut md, baseline_path, &results, threshold)?;
+    Ok(md)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pair_mut_borrows_both_sides_regardless_of_order() {
+        let mut sites = vec![10, 20, 30, 40];
+        {
+            let (a, b) = pair_mut(&mut sites, 1, 3);
+            *a += 1;
+            *b += 1;
+        }
+        assert_eq!(sites, vec![10, 21, 30, 41]);
+
+        let mut sites = vec![10, 20, 30, 40];
+        {
+            let (a, b) = pair_mut(&mut sites, 3, 1);
+            *a += 1;
+            *b += 1;
+        }
+        assert_eq!(sites, vec![10, 21, 30, 41]);
+    }
+
+    #[test]
+    #[should_panic(expected = "a site can't gossip with itself")]
+    fn pair_mut_rejects_a_site_pairing_with_itself() {
+        let mut sites = vec![10, 20, 30];
+        pair_mut(&mut sites, 1, 1);
+    }
+}
@@ -0,0 +1,361 @@
+//! Loads named benchmark scenarios from `workloads/*.json` instead of the
+//! single hardcoded "automerge paper" dataset, and records machine-readable
+//! results so a CI job can diff a run against a recorded baseline.
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fmt::Write as _,
+    fs,
+    io::{self, Write},
+    path::Path,
+};
+
+use crate::MarkValue;
+
+/// One mutation applied at a single site.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Op {
+    TextInsert { pos: usize, text: String },
+    TextDel { pos: usize, len: usize },
+    ListInsert { pos: usize, value: i32 },
+    ListDel { pos: usize, len: usize },
+    MapInsert { key: String, value: i32 },
+    MapDel { key: String },
+    Mark { start: usize, end: usize, key: String, value: MarkValue },
+    Unmark { start: usize, end: usize, key: String },
+}
+
+/// A single step in a site's op stream, tagged with which site performs it.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SiteOp {
+    pub site: usize,
+    #[serde(flatten)]
+    pub op: Op,
+}
+
+/// When to exchange state between sites while replaying `ops`: `at` is the
+/// index into `Workload::ops` after which the merge fires, so a schedule
+/// entry always ties to a specific point in the op stream rather than to its
+/// own position in `merge_schedule`. Every runner that reads a schedule
+/// today only ever has two replicas to sync, so a step names no sites of its
+/// own — there's only one pair it could mean.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct MergeStep {
+    pub at: usize,
+}
+
+/// Which numbers the runner should collect while replaying a workload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Metric {
+    DocSize,
+    MergeBytes,
+    WallTime,
+}
+
+/// A named benchmark scenario: how many sites, what each site does, when
+/// they sync, and which metrics matter for this scenario.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Workload {
+    pub name: String,
+    pub sites: usize,
+    pub ops: Vec<SiteOp>,
+    #[serde(default)]
+    pub merge_schedule: Vec<MergeStep>,
+    pub metrics: Vec<Metric>,
+}
+
+impl Workload {
+    /// Loads and parses a workload description from `path`.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let raw = fs::read_to_string(path)?;
+        serde_json::from_str(&raw)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+    }
+}
+
+/// One backend's measurements for a single workload run, in the shape
+/// written to the machine-readable results file.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct WorkloadResult {
+    pub workload: String,
+    pub backend: String,
+    pub gc: bool,
+    pub compression: bool,
+    pub doc_size: Option<usize>,
+    pub merge_bytes: Option<u64>,
+    pub wall_time_ms: Option<u128>,
+    /// Whether every replica agreed once fully synced. `None` for runs that
+    /// don't check convergence (e.g. a single-site doc-size report).
+    pub converged: Option<bool>,
+    /// The largest any replica's encoded snapshot grew mid-run. `None` for
+    /// runs that don't track per-site size, like the two-site reports.
+    pub peak_doc_size: Option<usize>,
+    /// Bytes of doc size attributable to rich-text formatting metadata.
+    /// `None` for workloads with no mark ops.
+    pub formatting_bytes: Option<usize>,
+    /// Unique bytes left after content-defined chunking dedups a backend's
+    /// accumulated snapshot history. `None` for runs that don't chunk
+    /// snapshots, like the doc-size and convergence reports.
+    pub unique_chunk_bytes: Option<u64>,
+}
+
+/// Writes `results` as pretty-printed JSON so CI can diff successive runs.
+pub fn write_results(path: impl AsRef<Path>, results: &[WorkloadResult]) -> io::Result<()> {
+    let mut file = fs::File::create(path)?;
+    file.write_all(serde_json::to_string_pretty(results)?.as_bytes())
+}
+
+/// A metric that moved beyond `threshold` between the baseline and current run.
+#[derive(Debug, Clone, Serialize)]
+pub struct Regression {
+    pub backend: String,
+    pub metric: &'static str,
+    pub baseline: f64,
+    pub current: f64,
+    pub percent_change: f64,
+}
+
+/// Compares `current` against the results recorded at `baseline_path`,
+/// flagging any backend whose doc size, merge bytes, timing, peak doc size,
+/// formatting bytes or unique chunk bytes grew by more than `threshold`
+/// (e.g. `0.05` for 5%).
+pub fn compare_against_baseline(
+    baseline_path: impl AsRef<Path>,
+    current: &[WorkloadResult],
+    threshold: f64,
+) -> io::Result<Vec<Regression>> {
+    let raw = fs::read_to_string(baseline_path)?;
+    let baseline: Vec<WorkloadResult> = serde_json::from_str(&raw)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+    // Keyed by (workload, backend): a baseline file holding rows for more
+    // than one workload for the same backend must not collapse onto a
+    // single row, or a regression check could compare against a stale
+    // workload's numbers entirely.
+    // Rows with no recorded metric (a gc/compression combination the backend
+    // skipped) are left out so they can't shadow the row that did run.
+    let by_workload_and_backend: HashMap<(&str, &str), &WorkloadResult> = baseline
+        .iter()
+        .filter(|r| {
+            r.doc_size.is_some()
+                || r.merge_bytes.is_some()
+                || r.wall_time_ms.is_some()
+                || r.peak_doc_size.is_some()
+                || r.formatting_bytes.is_some()
+                || r.unique_chunk_bytes.is_some()
+        })
+        .map(|r| ((r.workload.as_str(), r.backend.as_str()), r))
+        .collect();
+
+    let mut regressions = Vec::new();
+    for result in current {
+        let Some(base) =
+            by_workload_and_backend.get(&(result.workload.as_str(), result.backend.as_str()))
+        else {
+            continue;
+        };
+        for (metric, base_value, current_value) in [
+            ("doc_size", base.doc_size.map(|v| v as f64), result.doc_size.map(|v| v as f64)),
+            (
+                "merge_bytes",
+                base.merge_bytes.map(|v| v as f64),
+                result.merge_bytes.map(|v| v as f64),
+            ),
+            (
+                "wall_time_ms",
+                base.wall_time_ms.map(|v| v as f64),
+                result.wall_time_ms.map(|v| v as f64),
+            ),
+            (
+                "peak_doc_size",
+                base.peak_doc_size.map(|v| v as f64),
+                result.peak_doc_size.map(|v| v as f64),
+            ),
+            (
+                "formatting_bytes",
+                base.formatting_bytes.map(|v| v as f64),
+                result.formatting_bytes.map(|v| v as f64),
+            ),
+            (
+                "unique_chunk_bytes",
+                base.unique_chunk_bytes.map(|v| v as f64),
+                result.unique_chunk_bytes.map(|v| v as f64),
+            ),
+        ] {
+            let (Some(base_value), Some(current_value)) = (base_value, current_value) else {
+                continue;
+            };
+            if base_value <= 0.0 {
+                continue;
+            }
+            let percent_change = (current_value - base_value) / base_value;
+            if percent_change > threshold {
+                regressions.push(Regression {
+                    backend: result.backend.clone(),
+                    metric,
+                    baseline: base_value,
+                    current: current_value,
+                    percent_change,
+                });
+            }
+        }
+    }
+    Ok(regressions)
+}
+
+/// Appends a baseline-regression summary to `report`: "No regressions" if
+/// `current` stayed within `threshold` (e.g. `0.05` for 5%) of every metric
+/// recorded at `baseline_path`, otherwise one line per regressed metric.
+/// No-op if `baseline_path` is `None`.
+pub fn append_regression_report(
+    report: &mut String,
+    baseline_path: Option<&str>,
+    current: &[WorkloadResult],
+    threshold: f64,
+) -> io::Result<()> {
+    let Some(baseline_path) = baseline_path else {
+        return Ok(());
+    };
+    let regressions = compare_against_baseline(baseline_path, current, threshold)?;
+    if regressions.is_empty() {
+        report.push_str("\nNo regressions vs baseline.\n");
+    } else {
+        report.push_str("\nRegressions vs baseline:\n");
+        for r in regressions {
+            let _ = writeln!(
+                report,
+                "- {} {}: {:.0} -> {:.0} ({:+.1}%)",
+                r.backend, r.metric, r.baseline, r.current, r.percent_change * 100.0
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("crdt-bench-native-test-{}-{name}", std::process::id()))
+    }
+
+    #[test]
+    fn workload_load_parses_ops_merge_schedule_and_metrics() {
+        let path = temp_path("workload.json");
+        let json = r#"{
+            "name": "demo",
+            "sites": 2,
+            "metrics": ["doc_size", "merge_bytes"],
+            "ops": [
+                { "site": 0, "op": "text_insert", "pos": 0, "text": "hi" },
+                { "site": 1, "op": "map_insert", "key": "k", "value": 1 }
+            ],
+            "merge_schedule": [{ "at": 1 }]
+        }"#;
+        fs::write(&path, json).unwrap();
+        let workload = Workload::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(workload.name, "demo");
+        assert_eq!(workload.sites, 2);
+        assert_eq!(workload.metrics, vec![Metric::DocSize, Metric::MergeBytes]);
+        assert_eq!(workload.merge_schedule, vec![MergeStep { at: 1 }]);
+        assert!(matches!(workload.ops[0], SiteOp { site: 0, op: Op::TextInsert { .. } }));
+        assert!(matches!(workload.ops[1], SiteOp { site: 1, op: Op::MapInsert { .. } }));
+    }
+
+    #[test]
+    fn workload_load_surfaces_invalid_json_as_io_error() {
+        let path = temp_path("invalid.json");
+        fs::write(&path, "not json").unwrap();
+        let err = Workload::load(&path).unwrap_err();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    fn result(workload: &str, backend: &str, doc_size: usize) -> WorkloadResult {
+        WorkloadResult {
+            workload: workload.to_string(),
+            backend: backend.to_string(),
+            gc: false,
+            compression: false,
+            doc_size: Some(doc_size),
+            merge_bytes: None,
+            wall_time_ms: None,
+            converged: None,
+            peak_doc_size: None,
+            formatting_bytes: None,
+            unique_chunk_bytes: None,
+        }
+    }
+
+    #[test]
+    fn write_results_round_trips_through_json() {
+        let path = temp_path("results.json");
+        write_results(&path, &[result("demo", "loro", 100)]).unwrap();
+
+        let raw = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        let read_back: Vec<WorkloadResult> = serde_json::from_str(&raw).unwrap();
+
+        assert_eq!(read_back.len(), 1);
+        assert_eq!(read_back[0].workload, "demo");
+        assert_eq!(read_back[0].doc_size, Some(100));
+    }
+
+    #[test]
+    fn compare_against_baseline_flags_growth_past_threshold() {
+        let path = temp_path("baseline-regressed.json");
+        write_results(&path, &[result("demo", "loro", 100)]).unwrap();
+
+        let current = vec![result("demo", "loro", 120)];
+        let regressions = compare_against_baseline(&path, &current, 0.1).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(regressions[0].metric, "doc_size");
+        assert!((regressions[0].percent_change - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn compare_against_baseline_ignores_growth_within_threshold() {
+        let path = temp_path("baseline-within.json");
+        write_results(&path, &[result("demo", "loro", 100)]).unwrap();
+
+        let current = vec![result("demo", "loro", 105)];
+        let regressions = compare_against_baseline(&path, &current, 0.1).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(regressions.is_empty());
+    }
+
+    #[test]
+    fn compare_against_baseline_keys_by_workload_and_backend() {
+        let path = temp_path("baseline-multi.json");
+        write_results(
+            &path,
+            &[result("automerge-paper", "loro", 10), result("two-site-paper", "loro", 100)],
+        )
+        .unwrap();
+
+        // Same backend, but "two-site-paper" has its own baseline row that
+        // doesn't regress — must not fall back to "automerge-paper"'s row
+        // for the same backend and flag a false regression.
+        let current = vec![result("two-site-paper", "loro", 101)];
+        let regressions = compare_against_baseline(&path, &current, 0.05).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert!(regressions.is_empty());
+    }
+
+    #[test]
+    fn append_regression_report_is_a_no_op_without_a_baseline() {
+        let mut report = String::from("table\n");
+        append_regression_report(&mut report, None, &[], 0.05).unwrap();
+        assert_eq!(report, "table\n");
+    }
+}
@@ -0,0 +1,175 @@
+use std::{collections::HashMap, ops::Range};
+use yrs::{
+    types::text::YChange, Any, Doc, GetString, Map, MapRef, ReadTxn, StateVector, Text, TextRef,
+    Transact, Update,
+};
+
+use crate::{Crdt, MarkSpan, MarkValue, Unsupported};
+
+fn mark_value_to_any(value: &MarkValue) -> Any {
+    match value {
+        MarkValue::Bool(b) => Any::Bool(*b),
+        MarkValue::Str(s) => Any::String(s.as_str().into()),
+    }
+}
+
+fn any_to_mark_value(value: &Any) -> Option<MarkValue> {
+    match value {
+        Any::Bool(b) => Some(MarkValue::Bool(*b)),
+        Any::String(s) => Some(MarkValue::Str(s.to_string())),
+        _ => None,
+    }
+}
+
+pub struct YrsDoc {
+    doc: Doc,
+    text: TextRef,
+    map: MapRef,
+}
+
+impl Crdt for YrsDoc {
+    type Version = StateVector;
+
+    fn name() -> &'static str {
+        "yrs"
+    }
+
+    fn create(_gc: bool, _compression: bool) -> Self {
+        let doc = Doc::new();
+        let text = doc.get_or_insert_text("text");
+        let map = doc.get_or_insert_map("map");
+        YrsDoc { doc, text, map }
+    }
+
+    fn gc(&self) -> Result<bool, bool> {
+        Err(false)
+    }
+
+    fn compression(&self) -> Result<bool, bool> {
+        Err(false)
+    }
+
+    fn text_insert(&mut self, pos: usize, text: &str) {
+        let mut txn = self.doc.transact_mut();
+        self.text.insert(&mut txn, pos as u32, text);
+    }
+
+    fn text_del(&mut self, pos: usize, len: usize) {
+        let mut txn = self.doc.transact_mut();
+        self.text.remove_range(&mut txn, pos as u32, len as u32);
+    }
+
+    fn get_text(&mut self) -> Box<str> {
+        let txn = self.doc.transact();
+        self.text.get_string(&txn).into_boxed_str()
+    }
+
+    fn list_insert(&mut self, _pos: usize, _num: i32) {
+        unimplemented!("yrs backend benchmarks text/map, not the array type")
+    }
+
+    fn list_del(&mut self, _pos: usize, _len: usize) {
+        unimplemented!("yrs backend benchmarks text/map, not the array type")
+    }
+
+    fn get_list(&mut self) -> Vec<i32> {
+        unimplemented!("yrs backend benchmarks text/map, not the array type")
+    }
+
+    fn map_insert(&mut self, key: &str, num: i32) -> Result<(), Unsupported> {
+        let mut txn = self.doc.transact_mut();
+        self.map.insert(&mut txn, key, num as i64);
+        Ok(())
+    }
+
+    fn map_del(&mut self, key: &str) -> Result<(), Unsupported> {
+        let mut txn = self.doc.transact_mut();
+        self.map.remove(&mut txn, key);
+        Ok(())
+    }
+
+    fn get_map(&mut self) -> Result<HashMap<String, i32>, Unsupported> {
+        let txn = self.doc.transact();
+        Ok(self
+            .map
+            .iter(&txn)
+            .filter_map(|(key, value)| match value.to_json(&txn) {
+                lib0::any::Any::BigInt(n) => Some((key.to_owned(), n as i32)),
+                lib0::any::Any::Number(n) => Some((key.to_owned(), n as i32)),
+                _ => None,
+            })
+            .collect())
+    }
+
+    fn mark(&mut self, range: Range<usize>, key: &str, value: MarkValue) -> Result<(), Unsupported> {
+        let mut txn = self.doc.transact_mut();
+        let attrs = yrs::types::Attrs::from([(key.into(), mark_value_to_any(&value))]);
+        self.text
+            .format(&mut txn, range.start as u32, (range.end - range.start) as u32, attrs);
+        Ok(())
+    }
+
+    fn unmark(&mut self, range: Range<usize>, key: &str) -> Result<(), Unsupported> {
+        // yrs clears a formatting attribute by setting it back to null over the range.
+        let mut txn = self.doc.transact_mut();
+        let attrs = yrs::types::Attrs::from([(key.into(), Any::Null)]);
+        self.text
+            .format(&mut txn, range.start as u32, (range.end - range.start) as u32, attrs);
+        Ok(())
+    }
+
+    fn get_marks(&mut self) -> Result<Vec<MarkSpan>, Unsupported> {
+        let txn = self.doc.transact();
+        let mut spans = Vec::new();
+        let mut pos = 0usize;
+        for diff in self.text.diff(&txn, YChange::identity) {
+            let len = diff.insert.len();
+            if let Some(attrs) = diff.attributes {
+                for (key, value) in attrs.iter() {
+                    if let Some(value) = any_to_mark_value(value) {
+                        spans.push(MarkSpan {
+                            start: pos,
+                            end: pos + len,
+                            key: key.to_string(),
+                            value,
+                        });
+                    }
+                }
+            }
+            pos += len;
+        }
+        Ok(spans)
+    }
+
+    fn encode_full(&mut self) -> Vec<u8> {
+        let txn = self.doc.transact();
+        txn.encode_state_as_update_v2(&StateVector::default())
+    }
+
+    fn decode_full(&mut self, update: &[u8]) {
+        let mut txn = self.doc.transact_mut();
+        txn.apply_update(Update::decode_v2(update).unwrap());
+    }
+
+    fn encode_delta(&self, since: &Self::Version) -> Vec<u8> {
+        self.doc.transact().encode_state_as_update_v2(since)
+    }
+
+    fn apply_delta(&mut self, data: &[u8]) {
+        self.decode_full(data);
+    }
+
+    fn merge(&mut self, other: &mut Self) -> u64 {
+        let a_sv = self.version();
+        let b_sv = other.version();
+        let a_to_b = self.encode_delta(&b_sv);
+        let b_to_a = other.encode_delta(&a_sv);
+        self.apply_delta(&b_to_a);
+        other.apply_delta(&a_to_b);
+        (a_to_b.len() + b_to_a.len()) as u64
+    }
+
+    fn version(&self) -> Self::Version {
+        self.doc.transact().state_vector()
+    }
+}